@@ -0,0 +1,104 @@
+use crate::summary::eis_parent;
+use crate::{Watcher, EIS_HEAD};
+use git2::{Commit, Oid};
+use std::collections::HashSet;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+// Keep every snapshot taken in the last 15 minutes...
+const KEEP_ALL_SECS: i64 = 15 * 60;
+// ...one per hour for the last day...
+const HOURLY_SECS: i64 = 24 * 60 * 60;
+// ...and one per day beyond that.
+
+#[derive(PartialEq, Eq, Hash)]
+enum Bucket {
+    Recent(Oid),
+    Hourly(i64),
+    Daily(i64),
+}
+
+fn bucket_for(now: i64, commit: &Commit) -> Bucket {
+    let time = commit.time().seconds();
+    let age = now - time;
+
+    if age < KEEP_ALL_SECS {
+        // Every recent snapshot gets its own bucket, i.e. is always kept.
+        Bucket::Recent(commit.id())
+    } else if age < HOURLY_SECS {
+        Bucket::Hourly(time / 3600)
+    } else {
+        Bucket::Daily(time / 86_400)
+    }
+}
+
+impl Watcher {
+    // Thins the EIS_HEAD history down to a logarithmic retention policy,
+    // re-parenting kept commits over dropped ones. Never prunes the merge
+    // base with the real `HEAD`.
+    pub fn compact(&self) -> Result<(), anyhow::Error> {
+        let Some(eis_head) = self.get_eis_head() else {
+            return Ok(());
+        };
+
+        let head = self.repo.head()?.target().unwrap();
+        let merge_base = self.repo.merge_base(eis_head, head)?;
+
+        let mut chain = vec![self.repo.find_commit(eis_head)?];
+        while chain.last().unwrap().id() != merge_base {
+            let parent = eis_parent(chain.last().unwrap())?;
+            chain.push(parent);
+        }
+
+        if chain.len() == 1 {
+            // Nothing but the merge base itself; nothing to compact.
+            return Ok(());
+        }
+
+        let now = SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs() as i64;
+
+        let mut seen = HashSet::new();
+        let mut keep = vec![true; chain.len()];
+        for (i, commit) in chain.iter().enumerate() {
+            if i == chain.len() - 1 {
+                // The merge base is always kept.
+                continue;
+            }
+
+            let bucket = bucket_for(now, commit);
+            if !seen.insert(bucket) {
+                keep[i] = false;
+            }
+        }
+
+        if keep.iter().all(|&k| k) {
+            return Ok(());
+        }
+
+        let mut kept: Vec<&Commit> = chain
+            .iter()
+            .enumerate()
+            .filter(|(i, _)| keep[*i])
+            .map(|(_, commit)| commit)
+            .collect();
+        kept.reverse(); // oldest (the merge base) first
+
+        let mut parent_oid = merge_base;
+        for commit in &kept[1..] {
+            let tree = commit.tree()?;
+            let parent = self.repo.find_commit(parent_oid)?;
+            parent_oid = self.repo.commit(
+                None,
+                &commit.author(),
+                &commit.committer(),
+                commit.message().unwrap_or("eis commit"),
+                &tree,
+                &[&parent],
+            )?;
+        }
+
+        self.repo
+            .reference(EIS_HEAD, parent_oid, true, "eis compact")?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,93 @@
+use git2::{Oid, Repository};
+use std::path::PathBuf;
+use std::sync::mpsc;
+use std::thread;
+
+pub(crate) struct DiffRequest {
+    pub(crate) parent: Oid,
+    pub(crate) child: Oid,
+}
+
+pub(crate) struct DiffResult {
+    pub(crate) child: Oid,
+    pub(crate) files_changed: usize,
+    pub(crate) insertions: usize,
+    pub(crate) deletions: usize,
+    pub(crate) patch: String,
+}
+
+// Spawns a thread with its own `Repository` handle that computes diffs off
+// a request channel and streams results back; skips pairs with equal trees.
+pub(crate) fn spawn(
+    repo_path: PathBuf,
+) -> Result<
+    (
+        mpsc::Sender<DiffRequest>,
+        mpsc::Receiver<DiffResult>,
+        thread::JoinHandle<Result<(), anyhow::Error>>,
+    ),
+    anyhow::Error,
+> {
+    let (request_tx, request_rx) = mpsc::channel::<DiffRequest>();
+    let (result_tx, result_rx) = mpsc::channel::<DiffResult>();
+
+    let handle = thread::spawn(move || -> Result<(), anyhow::Error> {
+        let repo = Repository::open(&repo_path)?;
+
+        while let Ok(request) = request_rx.recv() {
+            let parent_commit = repo.find_commit(request.parent)?;
+            let child_commit = repo.find_commit(request.child)?;
+
+            if parent_commit.tree_id() == child_commit.tree_id() {
+                continue;
+            }
+
+            let diff = repo.diff_tree_to_tree(
+                Some(&parent_commit.tree()?),
+                Some(&child_commit.tree()?),
+                None,
+            )?;
+            let stats = diff.stats()?;
+
+            let mut patch = String::new();
+            diff.print(git2::DiffFormat::Patch, |delta, _hunk, line| {
+                let status = match delta.status() {
+                    git2::Delta::Added => "+",
+                    git2::Delta::Deleted => "-",
+                    git2::Delta::Modified => "M",
+                    git2::Delta::Renamed => "R",
+                    git2::Delta::Copied => "C",
+                    git2::Delta::Ignored => "I",
+                    git2::Delta::Untracked => "U",
+                    git2::Delta::Typechange => "T",
+                    git2::Delta::Unreadable => "X",
+                    git2::Delta::Conflicted => "!",
+                    git2::Delta::Unmodified => " ",
+                };
+                patch.push_str(status);
+                patch.push(' ');
+                patch.push_str(&String::from_utf8_lossy(line.content()));
+                true
+            })?;
+
+            // The receiving end may have gone away (e.g. the caller only
+            // wanted the first few results); nothing to do but stop.
+            if result_tx
+                .send(DiffResult {
+                    child: request.child,
+                    files_changed: stats.files_changed(),
+                    insertions: stats.insertions(),
+                    deletions: stats.deletions(),
+                    patch,
+                })
+                .is_err()
+            {
+                break;
+            }
+        }
+
+        Ok(())
+    });
+
+    Ok((request_tx, result_rx, handle))
+}
@@ -0,0 +1,74 @@
+use crate::summary::eis_parent;
+use crate::Watcher;
+use anyhow::anyhow;
+use git2::Commit;
+
+impl Watcher {
+    // Promotes the snapshots on EIS_HEAD onto a new branch, leaving EIS_HEAD
+    // untouched.
+    pub fn graduate(&self, branch: &str, squash: bool) -> Result<(), anyhow::Error> {
+        let eis_head = self
+            .get_eis_head()
+            .ok_or_else(|| anyhow!("no eis history found, run `eis init`"))?;
+        let head_oid = self.repo.head()?.target().unwrap();
+        let branch_ref = format!("refs/heads/{}", branch);
+
+        if self.repo.find_reference(&branch_ref).is_ok() {
+            return Err(anyhow!("branch `{}` already exists", branch));
+        }
+
+        let new_head = if squash {
+            let tree = self.repo.find_commit(eis_head)?.tree()?;
+            let head_commit = self.repo.find_commit(head_oid)?;
+            let signature = self.repo.signature()?;
+
+            self.repo.commit(
+                None,
+                &signature,
+                &signature,
+                &format!("Graduate eis snapshots onto {}", branch),
+                &tree,
+                &[&head_commit],
+            )?
+        } else {
+            let merge_base = self.repo.merge_base(eis_head, head_oid)?;
+
+            let mut chain = vec![self.repo.find_commit(eis_head)?];
+            while chain.last().unwrap().id() != merge_base {
+                let parent = eis_parent(chain.last().unwrap())?;
+                chain.push(parent);
+            }
+            chain.pop(); // the merge base is already part of HEAD's history
+            chain.reverse(); // oldest to newest
+
+            // Collapse runs of snapshots that didn't actually change anything.
+            let mut distinct: Vec<&Commit> = Vec::new();
+            for commit in &chain {
+                if distinct.last().map(|c| c.tree_id()) != Some(commit.tree_id()) {
+                    distinct.push(commit);
+                }
+            }
+
+            let mut parent_oid = head_oid;
+            for commit in distinct {
+                let tree = commit.tree()?;
+                let parent = self.repo.find_commit(parent_oid)?;
+                parent_oid = self.repo.commit(
+                    None,
+                    &commit.author(),
+                    &commit.committer(),
+                    commit.message().unwrap_or("eis commit"),
+                    &tree,
+                    &[&parent],
+                )?;
+            }
+
+            parent_oid
+        };
+
+        self.repo.reference(&branch_ref, new_head, false, "eis graduate")?;
+        println!("Created branch `{}`", branch);
+
+        Ok(())
+    }
+}
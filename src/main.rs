@@ -1,12 +1,23 @@
 use anyhow::anyhow;
 use clap::{Parser, Subcommand};
 use git2::{Index, Oid, Repository};
+use notify::{Event as NotifyEvent, EventKind, RecommendedWatcher, RecursiveMode};
 use std::env::current_exe;
+use std::fs;
 use std::path::{Path, PathBuf};
+use std::pin::Pin;
 use std::time::Duration;
-use std::{fs, process};
 use sysinfo::{Pid, System, SystemExt};
-use tokio::time::interval;
+use tokio::sync::mpsc;
+use tokio::time::{interval, sleep, Sleep};
+
+mod compact;
+mod diff_worker;
+mod graduate;
+mod restore;
+mod summary;
+
+use restore::RestoreMode;
 
 #[derive(Debug, Parser)]
 #[command(author, version, about)]
@@ -29,6 +40,29 @@ enum Command {
     /// Check whether the current directory is an eis repository
     /// and whether the daemon is currently running
     Status,
+    /// Print the diffs for the last ten eis snapshots
+    Summarize,
+    /// Thin out the EIS_HEAD history, keeping fewer snapshots the further
+    /// back in time they are
+    Compact,
+    /// Roll the working tree back to an eis snapshot
+    Restore {
+        /// Snapshot to restore to: a full/abbreviated oid, or `@{N}` for N
+        /// snapshots back along the EIS_HEAD parent chain
+        target: String,
+        /// Write the snapshot to a throwaway ref instead of the working directory
+        #[arg(long)]
+        into_stash: bool,
+    },
+    /// Promote eis snapshots into real commits on a new branch
+    Graduate {
+        /// Name of the branch to create
+        branch: String,
+        /// Collapse all snapshots into a single commit instead of replaying
+        /// each distinct snapshot as its own commit
+        #[arg(long)]
+        squash: bool,
+    },
 }
 
 fn is_daemon_running(pid_path: &Path) -> Result<bool, anyhow::Error> {
@@ -112,29 +146,110 @@ async fn main() -> Result<(), anyhow::Error> {
 
             Ok(())
         }
+        Command::Summarize => Watcher::new(&cwd)?.summarize(),
+        Command::Compact => Watcher::new(&cwd)?.compact(),
+        Command::Graduate { branch, squash } => Watcher::new(&cwd)?.graduate(&branch, squash),
+        Command::Restore { target, into_stash } => {
+            let mode = if into_stash {
+                RestoreMode::IntoStash
+            } else {
+                RestoreMode::Checkout
+            };
+
+            Watcher::new(&cwd)?.restore(&target, mode)
+        }
     }
 }
 
+// How long to wait for a burst of filesystem events to go quiet before
+// snapshotting. A bulk `git checkout` or an editor's save-all touches many
+// files in quick succession; without this we'd produce one EIS commit per
+// file instead of one for the whole burst.
+const DEBOUNCE_WINDOW: Duration = Duration::from_millis(500);
+
+// Safety net in case filesystem events are dropped or missed entirely
+// (e.g. on filesystems notify can't watch natively).
+const FALLBACK_TICK: Duration = Duration::from_secs(30);
+
 async fn daemon(cwd: PathBuf) -> Result<(), anyhow::Error> {
     let watcher = Watcher::new(&cwd)?;
-    let mut interval = interval(Duration::from_secs(5));
 
-    // Sets up ctrl-c handler so we can add the last changes before exiting
-    ctrlc::set_handler(move || {
-        let watcher = Watcher::new(&cwd).unwrap();
-        watcher.watch().unwrap();
-        process::exit(0);
-    })?;
+    let (fs_tx, mut fs_rx) = mpsc::unbounded_channel();
+    let _fs_watcher = spawn_fs_watcher(cwd.clone(), fs_tx)?;
+
+    let mut fallback = interval(FALLBACK_TICK);
+    let mut debounce: Option<Pin<Box<Sleep>>> = None;
 
     loop {
-        interval.tick().await;
-        watcher.watch()?;
+        tokio::select! {
+            // A relevant filesystem event arrived: (re)start the debounce
+            // window instead of snapshotting immediately.
+            Some(()) = fs_rx.recv() => {
+                debounce = Some(Box::pin(sleep(DEBOUNCE_WINDOW)));
+            }
+            // The debounce window elapsed with no further events: the burst
+            // is over, so snapshot now.
+            _ = async { debounce.as_mut().unwrap().await }, if debounce.is_some() => {
+                debounce = None;
+                watcher.watch()?;
+            }
+            // Periodic fallback tick in case events were missed.
+            _ = fallback.tick() => {
+                watcher.watch()?;
+            }
+            // Take one last snapshot before exiting.
+            _ = tokio::signal::ctrl_c() => {
+                watcher.watch()?;
+                return Ok(());
+            }
+        }
     }
 }
 
-const EIS_HEAD: &str = "EIS_HEAD";
-struct Watcher {
-    repo: Repository,
+// Starts a recursive filesystem watch on `cwd`, forwarding a notification on
+// `tx` for every create/modify/remove/rename event that isn't under `.git/`
+// or `.eis/` (both of which are touched by our own snapshotting and would
+// otherwise trigger an infinite loop of self-watches).
+//
+// The returned watcher must be kept alive for as long as events should keep
+// flowing; dropping it stops the watch.
+fn spawn_fs_watcher(
+    cwd: PathBuf,
+    tx: mpsc::UnboundedSender<()>,
+) -> Result<RecommendedWatcher, anyhow::Error> {
+    let mut watcher = notify::recommended_watcher(move |res: notify::Result<NotifyEvent>| {
+        let Ok(event) = res else {
+            return;
+        };
+
+        if is_relevant_event(&event) {
+            let _ = tx.send(());
+        }
+    })?;
+
+    notify::Watcher::watch(&mut watcher, &cwd, RecursiveMode::Recursive)?;
+
+    Ok(watcher)
+}
+
+fn is_relevant_event(event: &NotifyEvent) -> bool {
+    if !matches!(
+        event.kind,
+        EventKind::Create(_) | EventKind::Modify(_) | EventKind::Remove(_)
+    ) {
+        return false;
+    }
+
+    event.paths.iter().any(|path| {
+        !path
+            .components()
+            .any(|c| c.as_os_str() == ".git" || c.as_os_str() == ".eis")
+    })
+}
+
+pub(crate) const EIS_HEAD: &str = "EIS_HEAD";
+pub(crate) struct Watcher {
+    pub(crate) repo: Repository,
 }
 
 impl Watcher {
@@ -144,7 +259,7 @@ impl Watcher {
         Ok(Self { repo })
     }
 
-    fn watch(&self) -> Result<(), anyhow::Error> {
+    pub(crate) fn watch(&self) -> Result<(), anyhow::Error> {
         // Check if up to date and if not, we create a new one
         let eis_head = match self.get_eis_head() {
             Some(eis_head) if self.check_if_eis_head_is_up_to_date(eis_head)? => eis_head,
@@ -159,6 +274,8 @@ impl Watcher {
             }
         }
 
+        self.compact()?;
+
         Ok(())
     }
 
@@ -180,11 +297,24 @@ impl Watcher {
         Ok(commit)
     }
 
-    // Creates tree from temporary index of current repo state
+    // Creates a tree from the eis index, which is kept on disk across ticks
+    // instead of being cleared out after every use. libgit2 compares each
+    // entry's cached mtime and size against what's actually on disk, and
+    // skips re-reading and re-hashing a file when they match, so an
+    // unchanged file costs a stat() rather than a full read+hash. Entries
+    // whose on-disk mtime lands in the same second as the index's own write
+    // time are always treated as dirty and rehashed (the classic "racy
+    // git" case), so a change can never slip through unnoticed. Clearing
+    // the index every cycle, as we used to, threw all of this away and
+    // forced a full rehash of the tree on every tick.
     fn create_tree(&self) -> Result<Option<Oid>, anyhow::Error> {
         let index_file = Path::new(".git/eis-index");
         let mut index = Index::open(index_file)?;
         self.repo.set_index(&mut index)?;
+
+        // Refresh already-tracked entries first (this is what picks up
+        // modifications and removals), then pick up anything new.
+        index.update_all(["*"].iter(), None)?;
         index.add_all(["*"].iter(), git2::IndexAddOption::DEFAULT, None)?;
 
         if index.is_empty() {
@@ -192,8 +322,8 @@ impl Watcher {
         }
 
         let oid = index.write_tree()?;
-        // Clear up the index for next time
-        index.clear()?;
+        // Persist the refreshed stat cache so the next tick can skip
+        // unchanged files instead of rehashing the whole tree.
         index.write()?;
 
         Ok(Some(oid))
@@ -217,7 +347,7 @@ impl Watcher {
         Ok(eis_head.target().unwrap())
     }
 
-    fn get_eis_head(&self) -> Option<Oid> {
+    pub(crate) fn get_eis_head(&self) -> Option<Oid> {
         let head = self.repo.find_reference(&EIS_HEAD).ok()?;
 
         head.target()
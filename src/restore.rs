@@ -0,0 +1,78 @@
+use crate::summary::eis_parent;
+use crate::Watcher;
+use git2::build::CheckoutBuilder;
+use git2::{Commit, Repository};
+
+/// How `Watcher::restore` should apply the target snapshot.
+#[derive(Debug, Clone, Copy)]
+pub enum RestoreMode {
+    /// Snapshot the current state first, then check the target tree out
+    /// into the working directory.
+    Checkout,
+    /// Write the target tree to a throwaway ref instead of touching the
+    /// working directory.
+    IntoStash,
+}
+
+impl Watcher {
+    /// Rolls the working tree back to an earlier eis snapshot. `target` is
+    /// either a full/abbreviated oid of an EIS_HEAD ancestor, or `@{N}`
+    /// meaning N snapshots back along the EIS_HEAD parent chain (the same
+    /// chain `summarize` walks). This never moves the user's real `HEAD`,
+    /// only the files (or, in `IntoStash` mode, a throwaway ref).
+    pub fn restore(&self, target: &str, mode: RestoreMode) -> Result<(), anyhow::Error> {
+        let target_commit = self.resolve_snapshot(target)?;
+        let target_tree_id = target_commit.tree_id();
+
+        match mode {
+            RestoreMode::Checkout => {
+                // Snapshot the current state first so restoring never loses
+                // uncommitted work.
+                self.watch()?;
+
+                // `watch()` points this repo's libgit2-level index at
+                // `.git/eis-index` (see `create_tree`), and that can't be
+                // undone on the same handle. Reopen so the checkout below
+                // updates the real `.git/index`, not the eis one.
+                let repo = Repository::open(self.repo.path())?;
+                let target_tree = repo.find_tree(target_tree_id)?;
+
+                let mut checkout = CheckoutBuilder::new();
+                checkout.force();
+                repo.checkout_tree(target_tree.as_object(), Some(&mut checkout))?;
+            }
+            RestoreMode::IntoStash => {
+                let stash_ref = format!("refs/eis/restore/{}", target_commit.id());
+                self.repo.reference(
+                    &stash_ref,
+                    target_tree_id,
+                    true,
+                    "eis restore --into-stash",
+                )?;
+
+                println!("Wrote snapshot to {}", stash_ref);
+            }
+        }
+
+        Ok(())
+    }
+
+    fn resolve_snapshot(&self, target: &str) -> Result<Commit<'_>, anyhow::Error> {
+        if let Some(count) = target.strip_prefix("@{").and_then(|s| s.strip_suffix('}')) {
+            let count: u32 = count.parse()?;
+
+            let eis_head = self
+                .get_eis_head()
+                .ok_or_else(|| anyhow::anyhow!("no eis history found, run `eis init`"))?;
+
+            let mut commit = self.repo.find_commit(eis_head)?;
+            for _ in 0..count {
+                commit = eis_parent(&commit)?;
+            }
+
+            return Ok(commit);
+        }
+
+        Ok(self.repo.revparse_single(target)?.peel_to_commit()?)
+    }
+}
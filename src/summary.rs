@@ -1,4 +1,11 @@
+use crate::diff_worker::{self, DiffRequest};
 use crate::Watcher;
+use git2::Commit;
+
+// Shared with `restore`: walks one step back along the EIS_HEAD chain.
+pub(crate) fn eis_parent<'repo>(commit: &Commit<'repo>) -> Result<Commit<'repo>, anyhow::Error> {
+    Ok(commit.parent(0)?)
+}
 
 impl Watcher {
     pub fn summarize(&self) -> Result<(), anyhow::Error> {
@@ -7,39 +14,37 @@ impl Watcher {
             return Ok(());
         };
 
+        // Hand every diff this summary needs off to a background worker up
+        // front, then stream results back as they're ready instead of
+        // blocking on each one in turn.
+        let (requests, results, worker) = diff_worker::spawn(self.repo.path().to_path_buf())?;
+
         let mut eis_head_commit = self.repo.find_commit(eis_head)?;
         for _ in 0..10 {
-            let parent = if eis_head_commit.parent_count() > 1 {
-                eis_head_commit.parent(1)?
-            } else {
-                eis_head_commit.parent(0)?
-            };
-            println!("{}", eis_head_commit.id());
-            let diff = self.repo.diff_tree_to_tree(
-                Some(&parent.tree()?),
-                Some(&eis_head_commit.tree()?),
-                None,
-            )?;
-            diff.print(git2::DiffFormat::Patch, |delta, _hunk, line| {
-                let line = String::from_utf8_lossy(line.content());
-                let status = match delta.status() {
-                    git2::Delta::Added => "+",
-                    git2::Delta::Deleted => "-",
-                    git2::Delta::Modified => "M",
-                    git2::Delta::Renamed => "R",
-                    git2::Delta::Copied => "C",
-                    git2::Delta::Ignored => "I",
-                    git2::Delta::Untracked => "U",
-                    git2::Delta::Typechange => "T",
-                    git2::Delta::Unreadable => "X",
-                    git2::Delta::Conflicted => "!",
-                    git2::Delta::Unmodified => " ",
-                };
-                print!("{} {}", status, line);
-                true
+            let parent = eis_parent(&eis_head_commit)?;
+            requests.send(DiffRequest {
+                parent: parent.id(),
+                child: eis_head_commit.id(),
             })?;
             eis_head_commit = parent;
         }
+        drop(requests);
+
+        while let Ok(result) = results.recv() {
+            println!("{}", result.child);
+            println!(
+                "{} file(s) changed, +{} -{}",
+                result.files_changed, result.insertions, result.deletions
+            );
+            print!("{}", result.patch);
+        }
+
+        // The channel only closes when the worker thread exits; if it exited
+        // early because a diff failed, surface that instead of silently
+        // printing a truncated summary.
+        worker
+            .join()
+            .map_err(|_| anyhow::anyhow!("diff worker thread panicked"))??;
 
         Ok(())
     }